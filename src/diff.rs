@@ -0,0 +1,441 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use serde::Serialize;
+
+use crate::{
+    oredict, GTRecipeStatus, ItemStack, ShapedOredictRecipe, ShapedRecipe, ShapelessRecipe,
+};
+
+/// The generic counterpart to `main`'s GregTech-specific `RecipeBeforeAfter`,
+/// for recipe sources that don't group by machine.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum RecipeDiff<'a, R> {
+    Diff {
+        before: Vec<&'a R>,
+        after: Vec<&'a R>,
+    },
+    Same {
+        recipes: Vec<&'a R>,
+    },
+}
+
+fn group_by_key<'a, R, K: Eq + std::hash::Hash>(
+    recipes: &'a [R],
+    key_fn: impl Fn(&R) -> K,
+) -> HashMap<K, Vec<&'a R>> {
+    let mut map: HashMap<K, Vec<&'a R>> = HashMap::new();
+
+    for recipe in recipes {
+        map.entry(key_fn(recipe)).or_default().push(recipe);
+    }
+
+    map
+}
+
+/// Whether `a` and `b` contain the same recipes as a multiset, ignoring
+/// order. Dump ordering isn't stable across runs, so a positional `Vec`
+/// comparison would report spurious diffs for two sides that merely list
+/// the same conflicting/duplicate recipes in a different order.
+fn recipe_multiset_eq<R: Serialize>(a: &[&R], b: &[&R]) -> bool {
+    let key = |recipes: &[&R]| -> Vec<String> {
+        let mut keys: Vec<String> = recipes
+            .iter()
+            .map(|r| serde_json::to_string(r).unwrap_or_default())
+            .collect();
+        keys.sort();
+        keys
+    };
+
+    key(a) == key(b)
+}
+
+/// Compares two keyed recipe buckets and reports changes using the same
+/// `GTRecipeStatus` vocabulary `analyze` uses for GregTech: `Added`,
+/// `Removed`, `OutputsChanged`, `Conflicting` and `DuplicateRegistration`.
+fn analyze_generic<'a, K, R>(
+    before: &HashMap<K, Vec<&'a R>>,
+    after: &HashMap<K, Vec<&'a R>>,
+    outputs_eq: impl Fn(&R, &R) -> bool,
+) -> BTreeMap<GTRecipeStatus, Vec<RecipeDiff<'a, R>>>
+where
+    K: Eq + std::hash::Hash,
+    R: PartialEq + Serialize,
+{
+    let mut grouped: HashMap<GTRecipeStatus, Vec<RecipeDiff<'a, R>>> = HashMap::new();
+
+    let keys: HashSet<&K> = before.keys().chain(after.keys()).collect();
+
+    for key in keys {
+        let before_list = before.get(key);
+        let after_list = after.get(key);
+
+        // A group counts as conflicting as soon as either side has more than
+        // one recipe registered under the same key, regardless of whether
+        // the other side is missing entirely (Added/Removed) or present.
+        let conflicting = before_list.map(|v| v.len() > 1).unwrap_or(false)
+            || after_list.map(|v| v.len() > 1).unwrap_or(false);
+
+        if conflicting {
+            let mut combined: Vec<&'a R> = Vec::new();
+            combined.extend(before_list.into_iter().flatten().copied());
+            combined.extend(after_list.into_iter().flatten().copied());
+
+            let first = combined[0];
+            let all_same = combined.iter().all(|r| **r == *first);
+
+            let status = if all_same {
+                GTRecipeStatus::DuplicateRegistration
+            } else {
+                GTRecipeStatus::Conflicting
+            };
+
+            let diff = match (before_list, after_list) {
+                (Some(b), Some(a)) if recipe_multiset_eq(b, a) => {
+                    RecipeDiff::Same { recipes: b.clone() }
+                }
+                (Some(b), Some(a)) => RecipeDiff::Diff {
+                    before: b.clone(),
+                    after: a.clone(),
+                },
+                (Some(b), None) => RecipeDiff::Same { recipes: b.clone() },
+                (None, Some(a)) => RecipeDiff::Same { recipes: a.clone() },
+                (None, None) => unreachable!(),
+            };
+
+            grouped.entry(status).or_default().push(diff);
+            continue;
+        }
+
+        match (before_list, after_list) {
+            (Some(b), None) => {
+                grouped
+                    .entry(GTRecipeStatus::Removed)
+                    .or_default()
+                    .push(RecipeDiff::Same { recipes: b.clone() });
+            }
+            (None, Some(a)) => {
+                grouped
+                    .entry(GTRecipeStatus::Added)
+                    .or_default()
+                    .push(RecipeDiff::Same { recipes: a.clone() });
+            }
+            (Some(b), Some(a)) => {
+                let before_recipe = b[0];
+                let after_recipe = a[0];
+
+                if !outputs_eq(before_recipe, after_recipe) {
+                    grouped
+                        .entry(GTRecipeStatus::OutputsChanged)
+                        .or_default()
+                        .push(RecipeDiff::Diff {
+                            before: b.clone(),
+                            after: a.clone(),
+                        });
+                }
+            }
+            (None, None) => unreachable!(),
+        }
+    }
+
+    grouped
+        .into_iter()
+        .map(|(status, mut entries)| {
+            entries.sort_by_cached_key(|e| serde_json::to_string(e).unwrap_or_default());
+            (status, entries)
+        })
+        .collect()
+}
+
+fn shaped_key(recipe: &ShapedRecipe) -> Vec<Option<ItemStack>> {
+    recipe.item_inputs.clone()
+}
+
+fn shapeless_key(recipe: &ShapelessRecipe) -> Vec<ItemStack> {
+    let mut inputs: Vec<ItemStack> = recipe.item_inputs.iter().cloned().collect();
+    inputs.sort();
+    inputs
+}
+
+fn oredict_key(recipe: &ShapedOredictRecipe) -> Vec<Option<Vec<ItemStack>>> {
+    recipe
+        .item_inputs
+        .iter()
+        .map(|slot| {
+            slot.as_ref().map(|input| {
+                let mut candidates: Vec<ItemStack> = input.candidates().into_iter().collect();
+                candidates.sort();
+                candidates
+            })
+        })
+        .collect()
+}
+
+pub fn analyze_shaped<'a>(
+    before: &'a [ShapedRecipe],
+    after: &'a [ShapedRecipe],
+) -> BTreeMap<GTRecipeStatus, Vec<RecipeDiff<'a, ShapedRecipe>>> {
+    analyze_generic(
+        &group_by_key(before, shaped_key),
+        &group_by_key(after, shaped_key),
+        |a, b| a.item_output == b.item_output,
+    )
+}
+
+pub fn analyze_shapeless<'a>(
+    before: &'a [ShapelessRecipe],
+    after: &'a [ShapelessRecipe],
+) -> BTreeMap<GTRecipeStatus, Vec<RecipeDiff<'a, ShapelessRecipe>>> {
+    analyze_generic(
+        &group_by_key(before, shapeless_key),
+        &group_by_key(after, shapeless_key),
+        |a, b| a.item_output == b.item_output,
+    )
+}
+
+/// Like [`analyze_shaped`]/[`analyze_shapeless`], but also folds in
+/// ore-dict expansion conflicts detected within `after` (see
+/// [`oredict::find_conflicts`]) under the same `Conflicting` status, since
+/// those are a structural property of a single snapshot rather than a
+/// before/after change.
+pub fn analyze_oredict<'a>(
+    before: &'a [ShapedOredictRecipe],
+    after: &'a [ShapedOredictRecipe],
+) -> BTreeMap<GTRecipeStatus, Vec<RecipeDiff<'a, ShapedOredictRecipe>>> {
+    let mut statuses = analyze_generic(
+        &group_by_key(before, oredict_key),
+        &group_by_key(after, oredict_key),
+        |a, b| a.item_output == b.item_output,
+    );
+
+    // `oredict_key` already groups recipes with identical resolved candidate
+    // sets, so any collision between those has already been reported above
+    // as `Conflicting`/`DuplicateRegistration`. Drop each recipe already
+    // covered by one of those from any `find_conflicts` group it's also in,
+    // rather than keeping or dropping the whole group, since a group can
+    // partially overlap an already-reported pair without being subsumed by
+    // it. Recipes are compared by value, not by address: an unchanged group
+    // is reported with its `before` recipes only, which are distinct from
+    // the `after` recipes that `find_conflicts` sees.
+    let already_reported: Vec<&ShapedOredictRecipe> = statuses
+        .iter()
+        .filter(|(status, _)| {
+            matches!(
+                status,
+                GTRecipeStatus::Conflicting | GTRecipeStatus::DuplicateRegistration
+            )
+        })
+        .flat_map(|(_, diffs)| diffs.iter())
+        .flat_map(|diff| match diff {
+            RecipeDiff::Same { recipes } => recipes.clone(),
+            RecipeDiff::Diff { before, after } => {
+                before.iter().chain(after.iter()).copied().collect()
+            }
+        })
+        .collect();
+
+    let mut expansion_conflicts = oredict::find_conflicts(after)
+        .into_iter()
+        .filter_map(|conflict| {
+            let remaining: Vec<&ShapedOredictRecipe> = conflict
+                .recipes
+                .into_iter()
+                .filter(|r| !already_reported.contains(r))
+                .collect();
+
+            (remaining.len() > 1).then_some(RecipeDiff::Same { recipes: remaining })
+        })
+        .peekable();
+
+    if expansion_conflicts.peek().is_some() {
+        statuses
+            .entry(GTRecipeStatus::Conflicting)
+            .or_default()
+            .extend(expansion_conflicts);
+    }
+
+    statuses
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::{OredictInput, OredictStack};
+
+    use super::*;
+
+    fn item(name: &str) -> ItemStack {
+        ItemStack {
+            amount: 1,
+            metadata: 0,
+            unlocalized_name: Some(name.to_owned()),
+            localized_name: Some(name.to_owned()),
+        }
+    }
+
+    fn shapeless(inputs: &[&str], output: &str) -> ShapelessRecipe {
+        ShapelessRecipe {
+            item_inputs: inputs.iter().map(|n| item(n)).collect(),
+            item_output: item(output),
+        }
+    }
+
+    fn status_of<'a>(
+        statuses: &'a BTreeMap<GTRecipeStatus, Vec<RecipeDiff<'a, ShapelessRecipe>>>,
+        status: GTRecipeStatus,
+    ) -> &'a [RecipeDiff<'a, ShapelessRecipe>] {
+        statuses.get(&status).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    #[test]
+    fn reports_added_and_removed() {
+        let before = vec![shapeless(&["ore"], "ingot")];
+        let after = vec![shapeless(&["plank"], "stick")];
+
+        let statuses = analyze_shapeless(&before, &after);
+
+        assert_eq!(status_of(&statuses, GTRecipeStatus::Removed).len(), 1);
+        assert_eq!(status_of(&statuses, GTRecipeStatus::Added).len(), 1);
+    }
+
+    #[test]
+    fn reports_outputs_changed_when_the_same_inputs_yield_a_different_output() {
+        let before = vec![shapeless(&["ore"], "ingot")];
+        let after = vec![shapeless(&["ore"], "nugget")];
+
+        let statuses = analyze_shapeless(&before, &after);
+
+        assert_eq!(
+            status_of(&statuses, GTRecipeStatus::OutputsChanged).len(),
+            1
+        );
+        assert!(status_of(&statuses, GTRecipeStatus::Added).is_empty());
+        assert!(status_of(&statuses, GTRecipeStatus::Removed).is_empty());
+    }
+
+    #[test]
+    fn reports_no_diff_when_nothing_changed() {
+        let before = vec![shapeless(&["ore"], "ingot")];
+        let after = vec![shapeless(&["ore"], "ingot")];
+
+        let statuses = analyze_shapeless(&before, &after);
+
+        assert!(statuses.values().all(Vec::is_empty) || statuses.is_empty());
+    }
+
+    #[test]
+    fn flags_two_newly_added_recipes_with_the_same_inputs_as_conflicting() {
+        // Same key (inputs), no `before` counterpart, different outputs: a
+        // textbook grid collision that must not slip through as a plain
+        // `Added`.
+        let before: Vec<ShapelessRecipe> = Vec::new();
+        let after = vec![shapeless(&["ore"], "ingot"), shapeless(&["ore"], "nugget")];
+
+        let statuses = analyze_shapeless(&before, &after);
+
+        assert_eq!(status_of(&statuses, GTRecipeStatus::Conflicting).len(), 1);
+        assert!(status_of(&statuses, GTRecipeStatus::Added).is_empty());
+    }
+
+    #[test]
+    fn flags_duplicate_registrations_of_the_same_recipe_as_duplicate_not_conflicting() {
+        let before: Vec<ShapelessRecipe> = Vec::new();
+        let after = vec![shapeless(&["ore"], "ingot"), shapeless(&["ore"], "ingot")];
+
+        let statuses = analyze_shapeless(&before, &after);
+
+        assert_eq!(
+            status_of(&statuses, GTRecipeStatus::DuplicateRegistration).len(),
+            1
+        );
+        assert!(status_of(&statuses, GTRecipeStatus::Conflicting).is_empty());
+    }
+
+    fn oredict_slot(tag: &str, candidates: &[&str]) -> Option<OredictInput> {
+        Some(OredictInput {
+            oredict: Some(OredictStack {
+                oredict_names: HashSet::from([tag.to_owned()]),
+                candidates: candidates.iter().map(|n| item(n)).collect(),
+            }),
+            stack: None,
+        })
+    }
+
+    fn oredict_recipe(slot: Option<OredictInput>, output: &str) -> ShapedOredictRecipe {
+        ShapedOredictRecipe {
+            item_inputs: vec![slot],
+            item_output: item(output),
+        }
+    }
+
+    #[test]
+    fn oredict_conflict_with_identical_candidates_is_only_reported_once() {
+        // Both recipes resolve to the same `oredict_key`, so the generic
+        // key-grouping pass already reports them as `Conflicting`; the
+        // `find_conflicts` expansion pass must not report the same pair
+        // again.
+        let recipes = vec![
+            oredict_recipe(oredict_slot("ingotIron", &["iron_ingot"]), "plate"),
+            oredict_recipe(oredict_slot("ingotIron", &["iron_ingot"]), "scrap"),
+        ];
+
+        let statuses = analyze_oredict(&recipes, &recipes);
+
+        let conflicts = statuses
+            .get(&GTRecipeStatus::Conflicting)
+            .map(Vec::as_slice)
+            .unwrap_or(&[]);
+        assert_eq!(conflicts.len(), 1);
+    }
+
+    #[test]
+    fn reports_same_when_conflicting_recipes_are_reordered_across_dumps() {
+        // Same two conflicting recipes on both sides, just registered in a
+        // different order: dump ordering isn't stable, so this must not
+        // show up as a changed `Conflicting` entry.
+        let before = vec![shapeless(&["ore"], "ingot"), shapeless(&["ore"], "nugget")];
+        let after = vec![shapeless(&["ore"], "nugget"), shapeless(&["ore"], "ingot")];
+
+        let statuses = analyze_shapeless(&before, &after);
+
+        let conflicts = status_of(&statuses, GTRecipeStatus::Conflicting);
+        assert_eq!(conflicts.len(), 1);
+        assert!(matches!(conflicts[0], RecipeDiff::Same { .. }));
+    }
+
+    #[test]
+    fn oredict_conflict_sharing_only_some_recipes_with_an_already_reported_pair_is_not_duplicated()
+    {
+        // A and B resolve to the same `oredict_key` and are already reported
+        // as `Conflicting` by the generic pass. A, B and C all mutually
+        // overlap through `find_conflicts`'s looser candidate-overlap check,
+        // so the maximal clique is `[A, B, C]`; A and B must not be reported
+        // a second time, and C alone (without A or B) isn't a conflict.
+        let a = oredict_recipe(oredict_slot("ingotIron", &["iron_ingot"]), "plate");
+        let b = oredict_recipe(oredict_slot("ingotIron", &["iron_ingot"]), "scrap");
+        let c = oredict_recipe(
+            oredict_slot("ingotIron", &["iron_ingot", "gold_ingot"]),
+            "gear",
+        );
+        let recipes = vec![a, b, c];
+
+        let statuses = analyze_oredict(&recipes, &recipes);
+
+        let conflicts = statuses
+            .get(&GTRecipeStatus::Conflicting)
+            .map(Vec::as_slice)
+            .unwrap_or(&[]);
+
+        let reported_count: usize = conflicts
+            .iter()
+            .map(|diff| match diff {
+                RecipeDiff::Same { recipes } => recipes.len(),
+                RecipeDiff::Diff { before, after } => before.len() + after.len(),
+            })
+            .sum();
+
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(reported_count, 2);
+    }
+}
@@ -8,6 +8,15 @@ use clap::Parser;
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 
+mod diff;
+mod oredict;
+mod planner;
+mod search;
+
+use diff::RecipeDiff;
+use planner::{ItemKey, ProductionGraph};
+use search::ItemIndex;
+
 #[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq, PartialOrd, Ord)]
 struct ItemStack {
     #[serde(alias = "a")]
@@ -80,7 +89,7 @@ struct Machine {
     pub recipes: Vec<GTRecipe>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 struct ShapedRecipe {
     #[serde(alias = "iI")]
     pub item_inputs: Vec<Option<ItemStack>>,
@@ -88,7 +97,7 @@ struct ShapedRecipe {
     pub item_output: ItemStack,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 struct ShapelessRecipe {
     #[serde(alias = "iI")]
     pub item_inputs: HashSet<ItemStack>,
@@ -96,7 +105,7 @@ struct ShapelessRecipe {
     pub item_output: ItemStack,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 struct OredictStack {
     #[serde(alias = "dns")]
     pub oredict_names: HashSet<String>,
@@ -104,7 +113,7 @@ struct OredictStack {
     pub candidates: HashSet<ItemStack>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 struct OredictInput {
     #[serde(flatten)]
     oredict: Option<OredictStack>,
@@ -112,7 +121,7 @@ struct OredictInput {
     stack: Option<ItemStack>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 struct ShapedOredictRecipe {
     #[serde(alias = "iI")]
     pub item_inputs: Vec<Option<OredictInput>>,
@@ -181,6 +190,8 @@ impl Root {
     pub fn get_gt_recipes(
         &self,
     ) -> HashMap<&String, HashMap<(Vec<ItemStack>, Vec<FluidStack>), Vec<&GTRecipe>>> {
+        static EMPTY: Vec<Machine> = Vec::new();
+
         let gt = self
             .sources
             .iter()
@@ -188,7 +199,7 @@ impl Root {
                 RecipeSource::Gregtech { machines } => Some(machines),
                 _ => None,
             })
-            .unwrap();
+            .unwrap_or(&EMPTY);
 
         let mut per_machine = HashMap::new();
 
@@ -214,6 +225,42 @@ impl Root {
 
         per_machine
     }
+
+    pub fn get_oredict_recipes(&self) -> &Vec<ShapedOredictRecipe> {
+        static EMPTY: Vec<ShapedOredictRecipe> = Vec::new();
+
+        self.sources
+            .iter()
+            .find_map(|x| match x {
+                RecipeSource::ShapedOredict { recipes } => Some(recipes),
+                _ => None,
+            })
+            .unwrap_or(&EMPTY)
+    }
+
+    pub fn get_shaped_recipes(&self) -> &Vec<ShapedRecipe> {
+        static EMPTY: Vec<ShapedRecipe> = Vec::new();
+
+        self.sources
+            .iter()
+            .find_map(|x| match x {
+                RecipeSource::Shaped { recipes } => Some(recipes),
+                _ => None,
+            })
+            .unwrap_or(&EMPTY)
+    }
+
+    pub fn get_shapeless_recipes(&self) -> &Vec<ShapelessRecipe> {
+        static EMPTY: Vec<ShapelessRecipe> = Vec::new();
+
+        self.sources
+            .iter()
+            .find_map(|x| match x {
+                RecipeSource::Shapeless { recipes } => Some(recipes),
+                _ => None,
+            })
+            .unwrap_or(&EMPTY)
+    }
 }
 
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, clap::ValueEnum)]
@@ -462,6 +509,22 @@ fn analyze<'a>(
 #[derive(clap::Parser, Debug)]
 #[command(version, about, long_about = None)]
 struct Args {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(clap::Subcommand, Debug)]
+enum Command {
+    /// Diff two recex dumps and report recipe changes (the default workflow).
+    Diff(DiffArgs),
+    /// Look up which recipes produce/consume an item or fluid by name.
+    Search(SearchArgs),
+    /// Resolve a full crafting plan for an item, recursing into its inputs.
+    Plan(PlanArgs),
+}
+
+#[derive(clap::Args, Debug)]
+struct DiffArgs {
     #[arg(short, long, default_value = "analysis.json")]
     output: PathBuf,
 
@@ -481,9 +544,100 @@ struct Args {
     whitelist: Vec<GTRecipeStatus>,
 }
 
+#[derive(clap::Args, Debug)]
+struct SearchArgs {
+    #[doc = "Path to a recex dump to search"]
+    #[arg()]
+    root: PathBuf,
+
+    #[doc = "The item or fluid name to look up (typo-tolerant)"]
+    #[arg()]
+    query: String,
+}
+
+#[derive(clap::Args, Debug)]
+struct PlanArgs {
+    #[doc = "Path to a recex dump to plan against"]
+    #[arg()]
+    root: PathBuf,
+
+    #[doc = "The item name to craft (typo-tolerant); fluid names are not matched"]
+    #[arg()]
+    query: String,
+
+    #[doc = "How many of the item to produce"]
+    #[arg(default_value_t = 1)]
+    amount: i32,
+
+    #[doc = "Pin which recipe to use for an ambiguous item, as"]
+    #[doc = "`name[:metadata]=index`, where `index` is the 0-based position"]
+    #[doc = "among the item's producing recipes, in dump order. Repeatable."]
+    #[arg(short = 'c', long = "choice")]
+    choices: Vec<PlanChoice>,
+}
+
+/// One `--choice name[:metadata]=index` pinning a recipe for [`run_plan`].
+#[derive(Debug, Clone)]
+struct PlanChoice {
+    key: ItemKey,
+    index: usize,
+}
+
+impl std::str::FromStr for PlanChoice {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (item, index) = s
+            .split_once('=')
+            .ok_or_else(|| format!("expected `name[:metadata]=index`, got {s:?}"))?;
+
+        let (unlocalized_name, metadata) = match item.split_once(':') {
+            Some((name, metadata)) => (
+                name.to_owned(),
+                metadata
+                    .parse()
+                    .map_err(|_| format!("invalid metadata in {item:?}"))?,
+            ),
+            None => (item.to_owned(), 0),
+        };
+
+        let index = index
+            .parse()
+            .map_err(|_| format!("invalid candidate index in {s:?}"))?;
+
+        Ok(Self {
+            key: ItemKey::Item { unlocalized_name, metadata },
+            index,
+        })
+    }
+}
+
+/// `Diff` is the default subcommand: if the first argument isn't a known
+/// subcommand name (or a help/version flag), insert "diff" so that the
+/// original `recex-analyzer before.json after.json` invocation still works.
+fn args_with_default_subcommand() -> Vec<String> {
+    let mut raw_args: Vec<String> = std::env::args().collect();
+
+    let known = ["diff", "search", "plan", "help", "-h", "--help", "-V", "--version"];
+
+    if raw_args.len() > 1 && !known.contains(&raw_args[1].as_str()) {
+        raw_args.insert(1, "diff".to_owned());
+    }
+
+    raw_args
+}
+
 fn main() {
-    let args = Args::parse();
+    let args = Args::parse_from(args_with_default_subcommand());
+
+    match args.command {
+        Command::Diff(args) => run_diff(args),
+        Command::Search(args) => run_search(args),
+        Command::Plan(args) => run_plan(args),
+    }
+}
 
+fn run_diff(args: DiffArgs) {
     if args.blacklist.len() > 0 && args.whitelist.len() > 0 {
         panic!("cannot use --blacklist and --whitelist at the same time");
     }
@@ -538,9 +692,99 @@ fn main() {
         println!("{status}: {count}");
     }
 
+    println!("analyzing shaped/shapeless/ore-dict recipes");
+
+    let shaped = diff::analyze_shaped(before.get_shaped_recipes(), after.get_shaped_recipes());
+    let shapeless =
+        diff::analyze_shapeless(before.get_shapeless_recipes(), after.get_shapeless_recipes());
+    let shaped_oredict =
+        diff::analyze_oredict(before.get_oredict_recipes(), after.get_oredict_recipes());
+
     println!("writing {:?}", args.output);
 
-    let status = serde_json::to_string_pretty(&status).unwrap();
+    let output = DiffOutput {
+        gregtech: status,
+        shaped,
+        shapeless,
+        shaped_oredict,
+    };
+
+    let output = serde_json::to_string_pretty(&output).unwrap();
+
+    std::fs::write(&args.output, output).unwrap();
+}
+
+#[derive(Debug, Serialize)]
+struct DiffOutput<'a> {
+    gregtech: BTreeMap<&'a str, BTreeMap<GTRecipeStatus, Vec<RecipeBeforeAfter<'a>>>>,
+    shaped: BTreeMap<GTRecipeStatus, Vec<RecipeDiff<'a, ShapedRecipe>>>,
+    shapeless: BTreeMap<GTRecipeStatus, Vec<RecipeDiff<'a, ShapelessRecipe>>>,
+    #[serde(rename = "shapedOreDict")]
+    shaped_oredict: BTreeMap<GTRecipeStatus, Vec<RecipeDiff<'a, ShapedOredictRecipe>>>,
+}
+
+fn run_search(args: SearchArgs) {
+    let root = Root::load(&args.root);
+
+    println!("building item index");
+
+    let index = ItemIndex::build(&root);
+
+    let result = index.query(&args.query);
+
+    println!(
+        "matched names: {}",
+        result.matched_names.iter().join(", ")
+    );
+    println!(
+        "{} producer(s), {} consumer(s)",
+        result.producers.len(),
+        result.consumers.len()
+    );
+
+    let result = serde_json::to_string_pretty(&result).unwrap();
+
+    println!("{result}");
+}
+
+fn run_plan(args: PlanArgs) {
+    let root = Root::load(&args.root);
+
+    let index = ItemIndex::build(&root);
+    let query_result = index.query(&args.query);
+
+    let Some(target) = query_result
+        .producers
+        .iter()
+        .chain(query_result.consumers.iter())
+        .flat_map(|r| r.item_outputs.iter().chain(r.item_inputs.iter()))
+        .find(|stack| {
+            !stack.is_missing()
+                && [&stack.unlocalized_name, &stack.localized_name]
+                    .into_iter()
+                    .flatten()
+                    .any(|name| query_result.matched_names.contains(name))
+        })
+    else {
+        eprintln!("no known item matches {:?}", args.query);
+        return;
+    };
+
+    println!("building production graph");
+
+    let graph = ProductionGraph::build(&root);
+
+    println!("resolving plan for {}x {:?}", args.amount, target.unlocalized_name);
+
+    let choices: HashMap<ItemKey, usize> = args
+        .choices
+        .iter()
+        .map(|choice| (choice.key.clone(), choice.index))
+        .collect();
+
+    let plan = graph.plan_with_choices(target, args.amount, &choices);
+
+    let plan = serde_json::to_string_pretty(&plan).unwrap();
 
-    std::fs::write(&args.output, status).unwrap();
+    println!("{plan}");
 }
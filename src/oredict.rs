@@ -0,0 +1,292 @@
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+use crate::{GTRecipeStatus, ItemStack, OredictInput, ShapedOredictRecipe};
+
+impl OredictInput {
+    /// Expands this slot into the concrete set of items it can be
+    /// satisfied by: every candidate of its ore-dict tag, or just the
+    /// literal stack if it isn't an ore-dict slot.
+    pub fn candidates(&self) -> HashSet<ItemStack> {
+        if let Some(oredict) = &self.oredict {
+            oredict.candidates.clone()
+        } else if let Some(stack) = &self.stack {
+            HashSet::from([stack.clone()])
+        } else {
+            HashSet::new()
+        }
+    }
+}
+
+fn resolve_slots(recipe: &ShapedOredictRecipe) -> Vec<Option<HashSet<ItemStack>>> {
+    recipe
+        .item_inputs
+        .iter()
+        .map(|slot| slot.as_ref().map(OredictInput::candidates))
+        .collect()
+}
+
+fn same_shape(a: &ShapedOredictRecipe, b: &ShapedOredictRecipe) -> bool {
+    a.item_inputs.len() == b.item_inputs.len()
+        && a.item_inputs
+            .iter()
+            .zip(&b.item_inputs)
+            .all(|(sa, sb)| sa.is_some() == sb.is_some())
+}
+
+/// Whether every filled slot of `a` shares at least one candidate item
+/// with the corresponding slot of `b`, i.e. some physical grid of items
+/// could satisfy both recipes at once.
+fn slots_overlap(a: &[Option<HashSet<ItemStack>>], b: &[Option<HashSet<ItemStack>>]) -> bool {
+    a.len() == b.len()
+        && a.iter().zip(b.iter()).all(|(sa, sb)| match (sa, sb) {
+            (None, None) => true,
+            (Some(ca), Some(cb)) => ca.intersection(cb).next().is_some(),
+            _ => false,
+        })
+}
+
+/// Finds every maximal clique (a set of vertices where each pair is
+/// connected, and no further vertex can be added without breaking that)
+/// of size > 1 in the graph described by `adjacency`, via Bron-Kerbosch.
+///
+/// A recipe-overlap graph isn't transitive: A overlapping B and B
+/// overlapping C doesn't mean A overlaps C, so connected components would
+/// over-merge unrelated recipes. Cliques only ever group recipes that all
+/// mutually overlap.
+fn maximal_cliques(adjacency: &[HashSet<usize>]) -> Vec<Vec<usize>> {
+    fn expand(
+        r: &mut Vec<usize>,
+        mut p: HashSet<usize>,
+        mut x: HashSet<usize>,
+        adjacency: &[HashSet<usize>],
+        cliques: &mut Vec<Vec<usize>>,
+    ) {
+        if p.is_empty() && x.is_empty() {
+            if r.len() > 1 {
+                cliques.push(r.clone());
+            }
+            return;
+        }
+
+        for v in p.clone() {
+            r.push(v);
+            expand(
+                r,
+                p.intersection(&adjacency[v]).copied().collect(),
+                x.intersection(&adjacency[v]).copied().collect(),
+                adjacency,
+                cliques,
+            );
+            r.pop();
+            p.remove(&v);
+            x.insert(v);
+        }
+    }
+
+    let mut cliques = Vec::new();
+    expand(
+        &mut Vec::new(),
+        (0..adjacency.len()).collect(),
+        HashSet::new(),
+        adjacency,
+        &mut cliques,
+    );
+    cliques
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OredictConflict<'a> {
+    pub status: GTRecipeStatus,
+    pub recipes: Vec<&'a ShapedOredictRecipe>,
+}
+
+/// Finds groups of shaped ore-dict recipes that share a grid shape and
+/// whose candidate assignments overlap closely enough that a single
+/// physical arrangement of items could satisfy more than one of them,
+/// but which produce different outputs. This is the ore-dict analogue
+/// of the `Conflicting` status `analyze` already reports for GregTech.
+///
+/// Recipes are only ever grouped together if every pair among them
+/// overlaps (a clique), not merely if they're transitively connected
+/// through some chain of overlaps that don't all share a common item.
+pub fn find_conflicts(recipes: &[ShapedOredictRecipe]) -> Vec<OredictConflict<'_>> {
+    let resolved: Vec<_> = recipes.iter().map(resolve_slots).collect();
+
+    let mut adjacency = vec![HashSet::new(); recipes.len()];
+
+    for i in 0..recipes.len() {
+        for j in (i + 1)..recipes.len() {
+            if recipes[i].item_output == recipes[j].item_output {
+                continue;
+            }
+
+            if !same_shape(&recipes[i], &recipes[j]) {
+                continue;
+            }
+
+            if slots_overlap(&resolved[i], &resolved[j]) {
+                adjacency[i].insert(j);
+                adjacency[j].insert(i);
+            }
+        }
+    }
+
+    maximal_cliques(&adjacency)
+        .into_iter()
+        .map(|clique| OredictConflict {
+            status: GTRecipeStatus::Conflicting,
+            recipes: clique.into_iter().map(|i| &recipes[i]).collect(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::OredictStack;
+
+    use super::*;
+
+    fn item(name: &str) -> ItemStack {
+        ItemStack {
+            amount: 1,
+            metadata: 0,
+            unlocalized_name: Some(name.to_owned()),
+            localized_name: Some(name.to_owned()),
+        }
+    }
+
+    fn literal_slot(name: &str) -> Option<OredictInput> {
+        Some(OredictInput {
+            oredict: None,
+            stack: Some(item(name)),
+        })
+    }
+
+    fn oredict_slot(tag: &str, candidates: &[&str]) -> Option<OredictInput> {
+        Some(OredictInput {
+            oredict: Some(OredictStack {
+                oredict_names: HashSet::from([tag.to_owned()]),
+                candidates: candidates.iter().map(|n| item(n)).collect(),
+            }),
+            stack: None,
+        })
+    }
+
+    fn recipe(item_inputs: Vec<Option<OredictInput>>, output: &str) -> ShapedOredictRecipe {
+        ShapedOredictRecipe {
+            item_inputs,
+            item_output: item(output),
+        }
+    }
+
+    #[test]
+    fn no_conflict_when_candidates_dont_overlap() {
+        let recipes = vec![
+            recipe(vec![oredict_slot("plankWood", &["oak_plank"])], "chair"),
+            recipe(vec![oredict_slot("ingotIron", &["iron_ingot"])], "plate"),
+        ];
+
+        assert!(find_conflicts(&recipes).is_empty());
+    }
+
+    #[test]
+    fn no_conflict_when_shapes_differ() {
+        let recipes = vec![
+            recipe(vec![oredict_slot("ingotIron", &["iron_ingot"])], "plate"),
+            recipe(
+                vec![
+                    oredict_slot("ingotIron", &["iron_ingot"]),
+                    literal_slot("stick"),
+                ],
+                "tool",
+            ),
+        ];
+
+        assert!(find_conflicts(&recipes).is_empty());
+    }
+
+    #[test]
+    fn no_conflict_when_outputs_match() {
+        // Two registrations of the same recipe aren't an expansion conflict,
+        // just a duplicate (handled separately by the generic key diff).
+        let recipes = vec![
+            recipe(vec![oredict_slot("ingotIron", &["iron_ingot"])], "plate"),
+            recipe(vec![oredict_slot("ingotIron", &["iron_ingot"])], "plate"),
+        ];
+
+        assert!(find_conflicts(&recipes).is_empty());
+    }
+
+    #[test]
+    fn flags_overlapping_candidates_with_different_outputs() {
+        let recipes = vec![
+            recipe(
+                vec![oredict_slot("ingotIron", &["iron_ingot", "rusty_ingot"])],
+                "plate",
+            ),
+            recipe(vec![literal_slot("rusty_ingot")], "scrap"),
+        ];
+
+        let conflicts = find_conflicts(&recipes);
+
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].status, GTRecipeStatus::Conflicting);
+        assert_eq!(conflicts[0].recipes.len(), 2);
+    }
+
+    #[test]
+    fn does_not_transitively_group_a_chain_of_overlaps() {
+        // A overlaps B, B overlaps C, but A and C share no candidate and
+        // can never be satisfied by the same grid: they must be reported
+        // as two separate pairwise conflicts, not one group of three.
+        let recipes = vec![
+            recipe(vec![oredict_slot("tagAB", &["a", "b"])], "out_a"),
+            recipe(vec![oredict_slot("tagBC", &["b", "c"])], "out_b"),
+            recipe(vec![oredict_slot("tagCD", &["c", "d"])], "out_c"),
+        ];
+
+        let conflicts = find_conflicts(&recipes);
+
+        assert_eq!(conflicts.len(), 2);
+        assert!(conflicts.iter().all(|c| c.recipes.len() == 2));
+
+        let a = &recipes[0];
+        let b = &recipes[1];
+        let c = &recipes[2];
+
+        assert!(conflicts
+            .iter()
+            .any(|conflict| conflict.recipes.contains(&a) && conflict.recipes.contains(&b)));
+        assert!(conflicts
+            .iter()
+            .any(|conflict| conflict.recipes.contains(&b) && conflict.recipes.contains(&c)));
+        assert!(!conflicts
+            .iter()
+            .any(|conflict| conflict.recipes.contains(&a) && conflict.recipes.contains(&c)));
+    }
+
+    #[test]
+    fn groups_a_mutually_overlapping_clique_together() {
+        // A, B, and C all pairwise overlap via a shared candidate, so the
+        // maximal clique is the single group of three, not three separate
+        // pairs.
+        let recipes = vec![
+            recipe(vec![oredict_slot("ingotIron", &["iron_ingot"])], "plate"),
+            recipe(vec![oredict_slot("ingotIron", &["iron_ingot"])], "scrap"),
+            recipe(
+                vec![oredict_slot(
+                    "ingotIron",
+                    &["iron_ingot", "gold_ingot"],
+                )],
+                "gear",
+            ),
+        ];
+
+        let conflicts = find_conflicts(&recipes);
+
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].recipes.len(), 3);
+    }
+}
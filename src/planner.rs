@@ -0,0 +1,393 @@
+use std::collections::{hash_map::Entry, HashMap, HashSet};
+
+use serde::Serialize;
+
+use crate::{FluidStack, GTRecipe, ItemStack, RecipeSource, Root};
+
+/// Identifies an item or fluid by the same fields used to match a stack
+/// in a recipe slot, independent of which `GTRecipe` it came from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(tag = "kind")]
+pub enum ItemKey {
+    Item { unlocalized_name: String, metadata: i32 },
+    Fluid { unlocalized_name: String },
+}
+
+impl ItemKey {
+    pub fn from_item(stack: &ItemStack) -> Option<Self> {
+        if stack.is_missing() {
+            return None;
+        }
+
+        Some(Self::Item {
+            unlocalized_name: stack.unlocalized_name.clone().unwrap(),
+            metadata: stack.metadata,
+        })
+    }
+
+    pub fn from_fluid(stack: &FluidStack) -> Option<Self> {
+        if stack.is_missing() {
+            return None;
+        }
+
+        Some(Self::Fluid {
+            unlocalized_name: stack.unlocalized_name.clone().unwrap(),
+        })
+    }
+}
+
+fn div_ceil(amount: i32, per_craft: i32) -> i32 {
+    let per_craft = per_craft.max(1);
+    (amount + per_craft - 1) / per_craft
+}
+
+/// A recipe graph across every GregTech machine, where recipe A has an
+/// edge to recipe B whenever one of A's outputs matches one of B's
+/// inputs. Built once per [`Root`] and reused for every [`ProductionGraph::plan_with_choices`] call.
+pub struct ProductionGraph<'a> {
+    producers: HashMap<ItemKey, Vec<&'a GTRecipe>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PlanStep<'a> {
+    pub recipe: &'a GTRecipe,
+    /// How many times this recipe must run to satisfy everything that
+    /// depends on it.
+    pub times: i32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind")]
+pub enum PlanDiagnostic {
+    /// `item` is reachable from itself through its own inputs (e.g.
+    /// ingot <-> dust loops), so it can't be fully expanded.
+    Cycle { item: ItemKey },
+    /// More than one recipe produces `item` and the caller didn't pin a
+    /// choice, so expansion stopped at this item.
+    Ambiguous { item: ItemKey, candidate_count: usize },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Plan<'a> {
+    /// Recipes to craft, leaves (raw-material recipes) first.
+    pub steps: Vec<PlanStep<'a>>,
+    /// Total quantity of each item/fluid required across the whole plan.
+    pub totals: Vec<(ItemKey, i32)>,
+    pub diagnostics: Vec<PlanDiagnostic>,
+}
+
+impl<'a> ProductionGraph<'a> {
+    pub fn build(root: &'a Root) -> Self {
+        let mut producers: HashMap<ItemKey, Vec<&GTRecipe>> = HashMap::new();
+
+        for source in &root.sources {
+            let RecipeSource::Gregtech { machines } = source else {
+                continue;
+            };
+
+            for machine in machines {
+                for recipe in &machine.recipes {
+                    for stack in &recipe.item_outputs {
+                        if let Some(key) = ItemKey::from_item(stack) {
+                            producers.entry(key).or_default().push(recipe);
+                        }
+                    }
+
+                    for stack in &recipe.fluid_outputs {
+                        if let Some(key) = ItemKey::from_fluid(stack) {
+                            producers.entry(key).or_default().push(recipe);
+                        }
+                    }
+                }
+            }
+        }
+
+        // A recipe listing the same output in several stacks is still a
+        // single producer of it.
+        for recipes in producers.values_mut() {
+            recipes.dedup_by(|a, b| std::ptr::eq(*a, *b));
+        }
+
+        Self { producers }
+    }
+
+    /// Resolves a full crafting plan for `amount` of `target`, recursing
+    /// into its inputs until every branch bottoms out at a raw material
+    /// (an item with no producing recipe). `choices` lets the caller pin
+    /// which recipe to use for an item with more than one producer, keyed
+    /// by the 0-based index among the recipes producing that item, in dump
+    /// order. A [`PlanDiagnostic::Ambiguous`] reports only how many
+    /// producers an item has, not which recipes they are. An item with
+    /// several producers and no pinned choice is left unexpanded and
+    /// reported as `Ambiguous`; the rest of the plan is still resolved.
+    pub fn plan_with_choices(
+        &self,
+        target: &ItemStack,
+        amount: i32,
+        choices: &HashMap<ItemKey, usize>,
+    ) -> Plan<'a> {
+        let mut totals = HashMap::new();
+        let mut raw_steps = Vec::new();
+        let mut diagnostics = Vec::new();
+        let mut on_stack = HashSet::new();
+
+        if let Some(key) = ItemKey::from_item(target) {
+            self.resolve(
+                &key,
+                amount,
+                &mut on_stack,
+                &mut totals,
+                &mut raw_steps,
+                &mut diagnostics,
+                choices,
+            );
+        }
+
+        let mut order = Vec::new();
+        let mut merged: HashMap<*const GTRecipe, (&'a GTRecipe, i32)> = HashMap::new();
+
+        for (recipe, times) in raw_steps {
+            let ptr = recipe as *const GTRecipe;
+
+            match merged.entry(ptr) {
+                Entry::Occupied(mut e) => e.get_mut().1 += times,
+                Entry::Vacant(e) => {
+                    order.push(ptr);
+                    e.insert((recipe, times));
+                }
+            }
+        }
+
+        let steps = order
+            .into_iter()
+            .map(|ptr| {
+                let (recipe, times) = merged[&ptr];
+                PlanStep { recipe, times }
+            })
+            .collect();
+
+        Plan {
+            steps,
+            totals: totals.into_iter().collect(),
+            diagnostics,
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn resolve(
+        &self,
+        key: &ItemKey,
+        amount: i32,
+        on_stack: &mut HashSet<ItemKey>,
+        totals: &mut HashMap<ItemKey, i32>,
+        steps: &mut Vec<(&'a GTRecipe, i32)>,
+        diagnostics: &mut Vec<PlanDiagnostic>,
+        choices: &HashMap<ItemKey, usize>,
+    ) {
+        *totals.entry(key.clone()).or_insert(0) += amount;
+
+        let Some(candidates) = self.producers.get(key) else {
+            // No recipe produces this: it's a raw material leaf.
+            return;
+        };
+
+        if candidates.is_empty() {
+            return;
+        }
+
+        let recipe = if let Some(&idx) = choices.get(key) {
+            match candidates.get(idx) {
+                Some(r) => *r,
+                None => return,
+            }
+        } else if candidates.len() == 1 {
+            candidates[0]
+        } else {
+            diagnostics.push(PlanDiagnostic::Ambiguous {
+                item: key.clone(),
+                candidate_count: candidates.len(),
+            });
+            return;
+        };
+
+        if on_stack.contains(key) {
+            diagnostics.push(PlanDiagnostic::Cycle { item: key.clone() });
+            return;
+        }
+
+        on_stack.insert(key.clone());
+
+        let output_amount = recipe
+            .item_outputs
+            .iter()
+            .find(|o| ItemKey::from_item(o).as_ref() == Some(key))
+            .map(|o| o.amount)
+            .or_else(|| {
+                recipe
+                    .fluid_outputs
+                    .iter()
+                    .find(|o| ItemKey::from_fluid(o).as_ref() == Some(key))
+                    .map(|o| o.amount)
+            })
+            .unwrap_or(1);
+
+        let times = div_ceil(amount, output_amount);
+
+        for input in &recipe.item_inputs {
+            if let Some(input_key) = ItemKey::from_item(input) {
+                self.resolve(
+                    &input_key,
+                    input.amount * times,
+                    on_stack,
+                    totals,
+                    steps,
+                    diagnostics,
+                    choices,
+                );
+            }
+        }
+
+        for input in &recipe.fluid_inputs {
+            if let Some(input_key) = ItemKey::from_fluid(input) {
+                self.resolve(
+                    &input_key,
+                    input.amount * times,
+                    on_stack,
+                    totals,
+                    steps,
+                    diagnostics,
+                    choices,
+                );
+            }
+        }
+
+        on_stack.remove(key);
+
+        steps.push((recipe, times));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Machine, Root};
+
+    use super::*;
+
+    fn item(name: &str, amount: i32) -> ItemStack {
+        ItemStack {
+            amount,
+            metadata: 0,
+            unlocalized_name: Some(name.to_owned()),
+            localized_name: Some(name.to_owned()),
+        }
+    }
+
+    fn recipe(item_inputs: Vec<ItemStack>, item_outputs: Vec<ItemStack>) -> GTRecipe {
+        GTRecipe {
+            enabled: true,
+            duration: 1,
+            eut: 1,
+            item_inputs,
+            fluid_inputs: Vec::new(),
+            item_outputs,
+            fluid_outputs: Vec::new(),
+        }
+    }
+
+    fn root_with_recipes(recipes: Vec<GTRecipe>) -> Root {
+        Root {
+            sources: vec![RecipeSource::Gregtech {
+                machines: vec![Machine {
+                    name: "machine".to_owned(),
+                    recipes,
+                }],
+            }],
+        }
+    }
+
+    #[test]
+    fn detects_recipe_cycle() {
+        // ingot <-> dust, the classic GT loop.
+        let recipes = vec![
+            recipe(vec![item("dust", 1)], vec![item("ingot", 1)]),
+            recipe(vec![item("ingot", 1)], vec![item("dust", 1)]),
+        ];
+        let root = root_with_recipes(recipes);
+
+        let graph = ProductionGraph::build(&root);
+        let plan = graph.plan_with_choices(&item("ingot", 1), 1, &HashMap::new());
+
+        assert!(plan.diagnostics.iter().any(|d| matches!(
+            d,
+            PlanDiagnostic::Cycle { item } if *item == ItemKey::Item {
+                unlocalized_name: "ingot".to_owned(),
+                metadata: 0,
+            }
+        )));
+    }
+
+    #[test]
+    fn reports_ambiguous_producers_without_a_pinned_choice() {
+        let recipes = vec![
+            recipe(vec![item("ore", 1)], vec![item("ingot", 1)]),
+            recipe(vec![item("scrap", 2)], vec![item("ingot", 1)]),
+        ];
+        let root = root_with_recipes(recipes);
+
+        let graph = ProductionGraph::build(&root);
+        let plan = graph.plan_with_choices(&item("ingot", 1), 1, &HashMap::new());
+
+        assert!(plan.diagnostics.iter().any(|d| matches!(
+            d,
+            PlanDiagnostic::Ambiguous { item, candidate_count: 2 } if *item == ItemKey::Item {
+                unlocalized_name: "ingot".to_owned(),
+                metadata: 0,
+            }
+        )));
+        // Expansion stops at the ambiguous item, so no recipe was resolved.
+        assert!(plan.steps.is_empty());
+    }
+
+    #[test]
+    fn resolves_a_chain_leaves_first_and_scales_quantities() {
+        // ore -> ingot -> plate, each recipe consuming 2 of its input.
+        let recipes = vec![
+            recipe(vec![item("ore", 2)], vec![item("ingot", 1)]),
+            recipe(vec![item("ingot", 2)], vec![item("plate", 1)]),
+        ];
+        let root = root_with_recipes(recipes);
+
+        let graph = ProductionGraph::build(&root);
+        let plan = graph.plan_with_choices(&item("plate", 1), 4, &HashMap::new());
+
+        assert!(plan.diagnostics.is_empty());
+
+        // Leaves first: the ingot recipe (deeper in the chain) comes before
+        // the plate recipe that depends on it.
+        let outputs: Vec<&str> = plan
+            .steps
+            .iter()
+            .map(|s| {
+                s.recipe.item_outputs[0]
+                    .unlocalized_name
+                    .as_deref()
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(outputs, vec!["ingot", "plate"]);
+
+        // 4 plates need 4 crafts (1/craft) -> 8 ingots needed -> 8 crafts of
+        // the 1-ingot/craft recipe, each eating 2 ore -> 16 ore.
+        let ore_total = plan
+            .totals
+            .iter()
+            .find(|(k, _)| {
+                *k == ItemKey::Item {
+                    unlocalized_name: "ore".to_owned(),
+                    metadata: 0,
+                }
+            })
+            .map(|(_, amount)| *amount);
+        assert_eq!(ore_total, Some(16));
+    }
+}
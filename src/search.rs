@@ -0,0 +1,379 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use serde::Serialize;
+
+use crate::{GTRecipe, RecipeSource, Root};
+
+/// Returns the maximum Levenshtein distance a query term of the given
+/// length is allowed to match within, to keep short terms from matching
+/// almost anything.
+fn max_edit_distance(query_len: usize) -> usize {
+    match query_len {
+        0..=4 => 0,
+        5..=8 => 1,
+        _ => 2,
+    }
+}
+
+#[derive(Debug, Default)]
+struct TrieNode {
+    children: BTreeMap<char, TrieNode>,
+    // The original-cased name this node terminates, if any.
+    name: Option<String>,
+}
+
+impl TrieNode {
+    fn insert(&mut self, name: &str) {
+        let mut node = self;
+
+        for c in name.to_lowercase().chars() {
+            node = node.children.entry(c).or_default();
+        }
+
+        node.name = Some(name.to_owned());
+    }
+}
+
+/// A name found while searching the trie, along with how far it was
+/// from the query term.
+#[derive(Debug, Clone)]
+struct NameMatch {
+    name: String,
+    distance: usize,
+}
+
+// Classic trie + Levenshtein-row walk: https://stevehanov.ca/blog/?id=114
+fn search_trie(root: &TrieNode, query: &str) -> Vec<NameMatch> {
+    let query: Vec<char> = query.to_lowercase().chars().collect();
+    let max_distance = max_edit_distance(query.len());
+
+    let first_row: Vec<usize> = (0..=query.len()).collect();
+
+    let mut matches = Vec::new();
+
+    for (&ch, child) in &root.children {
+        search_trie_node(child, ch, &query, &first_row, max_distance, &mut matches);
+    }
+
+    matches.sort_by(|a, b| {
+        a.distance
+            .cmp(&b.distance)
+            .then_with(|| a.name.len().cmp(&b.name.len()))
+    });
+
+    matches
+}
+
+fn search_trie_node(
+    node: &TrieNode,
+    ch: char,
+    query: &[char],
+    previous_row: &[usize],
+    max_distance: usize,
+    matches: &mut Vec<NameMatch>,
+) {
+    let columns = query.len() + 1;
+    let mut current_row = Vec::with_capacity(columns);
+    current_row.push(previous_row[0] + 1);
+
+    for i in 1..columns {
+        let insert_cost = current_row[i - 1] + 1;
+        let delete_cost = previous_row[i] + 1;
+        let replace_cost = if query[i - 1] == ch {
+            previous_row[i - 1]
+        } else {
+            previous_row[i - 1] + 1
+        };
+
+        current_row.push(insert_cost.min(delete_cost).min(replace_cost));
+    }
+
+    if current_row[columns - 1] <= max_distance {
+        if let Some(name) = &node.name {
+            matches.push(NameMatch {
+                name: name.clone(),
+                distance: current_row[columns - 1],
+            });
+        }
+    }
+
+    // Prune: if nothing in this row is within budget, no deeper node can be either.
+    if *current_row.iter().min().unwrap() <= max_distance {
+        for (&next_ch, child) in &node.children {
+            search_trie_node(child, next_ch, query, &current_row, max_distance, matches);
+        }
+    }
+}
+
+/// Inverted index over every known item/fluid name in a [`Root`], mapping
+/// each name to the GT recipes that consume or produce it, with
+/// typo-tolerant lookup by name.
+pub struct ItemIndex<'a> {
+    names: TrieNode,
+    producers: HashMap<&'a str, Vec<&'a GTRecipe>>,
+    consumers: HashMap<&'a str, Vec<&'a GTRecipe>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QueryResult<'a> {
+    pub query: String,
+    pub matched_names: Vec<String>,
+    pub producers: Vec<&'a GTRecipe>,
+    pub consumers: Vec<&'a GTRecipe>,
+}
+
+fn index_names<'a>(
+    names: impl Iterator<Item = &'a Option<String>>,
+    trie: &mut TrieNode,
+    seen_names: &mut HashSet<String>,
+    map: &mut HashMap<&'a str, Vec<&'a GTRecipe>>,
+    recipe: &'a GTRecipe,
+) {
+    for name in names.flatten() {
+        if seen_names.insert(name.clone()) {
+            trie.insert(name);
+        }
+
+        map.entry(name.as_str()).or_default().push(recipe);
+    }
+}
+
+impl<'a> ItemIndex<'a> {
+    pub fn build(root: &'a Root) -> Self {
+        let mut names = TrieNode::default();
+        let mut seen_names = HashSet::new();
+        let mut producers: HashMap<&str, Vec<&GTRecipe>> = HashMap::new();
+        let mut consumers: HashMap<&str, Vec<&GTRecipe>> = HashMap::new();
+
+        for source in &root.sources {
+            let RecipeSource::Gregtech { machines } = source else {
+                continue;
+            };
+
+            for machine in machines {
+                for recipe in &machine.recipes {
+                    for stack in &recipe.item_inputs {
+                        if stack.is_missing() {
+                            continue;
+                        }
+
+                        index_names(
+                            [&stack.unlocalized_name, &stack.localized_name].into_iter(),
+                            &mut names,
+                            &mut seen_names,
+                            &mut consumers,
+                            recipe,
+                        );
+                    }
+
+                    for stack in &recipe.fluid_inputs {
+                        if stack.is_missing() {
+                            continue;
+                        }
+
+                        index_names(
+                            [&stack.unlocalized_name, &stack.localized_name].into_iter(),
+                            &mut names,
+                            &mut seen_names,
+                            &mut consumers,
+                            recipe,
+                        );
+                    }
+
+                    for stack in &recipe.item_outputs {
+                        if stack.is_missing() {
+                            continue;
+                        }
+
+                        index_names(
+                            [&stack.unlocalized_name, &stack.localized_name].into_iter(),
+                            &mut names,
+                            &mut seen_names,
+                            &mut producers,
+                            recipe,
+                        );
+                    }
+
+                    for stack in &recipe.fluid_outputs {
+                        if stack.is_missing() {
+                            continue;
+                        }
+
+                        index_names(
+                            [&stack.unlocalized_name, &stack.localized_name].into_iter(),
+                            &mut names,
+                            &mut seen_names,
+                            &mut producers,
+                            recipe,
+                        );
+                    }
+                }
+            }
+        }
+
+        Self {
+            names,
+            producers,
+            consumers,
+        }
+    }
+
+    /// Looks up producers/consumers for a human-typed name, tolerating
+    /// typos via a bounded edit-distance match against known names.
+    pub fn query(&self, query: &str) -> QueryResult<'a> {
+        let matches = search_trie(&self.names, query);
+
+        let mut producers = Vec::new();
+        let mut consumers = Vec::new();
+        let mut matched_names = Vec::new();
+        // A recipe can be indexed under more than one matched name (e.g. a
+        // repeated ingredient, or a stack whose unlocalized and localized
+        // names both match), so dedup by recipe pointer as it's collected.
+        let mut seen_producers = HashSet::new();
+        let mut seen_consumers = HashSet::new();
+
+        for m in &matches {
+            matched_names.push(m.name.clone());
+
+            if let Some(recipes) = self.producers.get(m.name.as_str()) {
+                for recipe in recipes {
+                    if seen_producers.insert(*recipe as *const GTRecipe) {
+                        producers.push(*recipe);
+                    }
+                }
+            }
+
+            if let Some(recipes) = self.consumers.get(m.name.as_str()) {
+                for recipe in recipes {
+                    if seen_consumers.insert(*recipe as *const GTRecipe) {
+                        consumers.push(*recipe);
+                    }
+                }
+            }
+        }
+
+        QueryResult {
+            query: query.to_owned(),
+            matched_names,
+            producers,
+            consumers,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn max_edit_distance_boundaries() {
+        assert_eq!(max_edit_distance(0), 0);
+        assert_eq!(max_edit_distance(4), 0);
+        assert_eq!(max_edit_distance(5), 1);
+        assert_eq!(max_edit_distance(8), 1);
+        assert_eq!(max_edit_distance(9), 2);
+        assert_eq!(max_edit_distance(100), 2);
+    }
+
+    fn names(results: &[NameMatch]) -> Vec<&str> {
+        results.iter().map(|m| m.name.as_str()).collect()
+    }
+
+    #[test]
+    fn short_query_requires_exact_match() {
+        let mut trie = TrieNode::default();
+        trie.insert("iron");
+        trie.insert("iron1"); // 5 chars, distance 1 from "iron"
+
+        // "iron" is 4 chars: budget is 0, so only the exact match counts.
+        assert_eq!(names(&search_trie(&trie, "iron")), vec!["iron"]);
+    }
+
+    #[test]
+    fn medium_query_tolerates_one_typo() {
+        let mut trie = TrieNode::default();
+        trie.insert("copper"); // 6 chars
+
+        // "ccpper" is one substitution away from "copper" (distance 1).
+        assert_eq!(names(&search_trie(&trie, "ccpper")), vec!["copper"]);
+        // "cppxr" is two edits away from "copper" (a dropped "o" and an
+        // "e" -> "x" substitution) and exceeds the
+        // distance-1 budget for a 5-char query.
+        assert!(search_trie(&trie, "cppxr").is_empty());
+    }
+
+    #[test]
+    fn long_query_tolerates_two_typos() {
+        let mut trie = TrieNode::default();
+        trie.insert("electromagnet"); // 13 chars
+
+        // Two substitutions away, within the distance-2 budget for >8 chars.
+        assert_eq!(
+            names(&search_trie(&trie, "electrumagnot")),
+            vec!["electromagnet"]
+        );
+    }
+
+    #[test]
+    fn matches_are_sorted_by_distance_then_length() {
+        let mut trie = TrieNode::default();
+        trie.insert("ironingot"); // exact match, 9 chars -> distance 0
+        trie.insert("irongot"); // missing two chars -> distance 2
+
+        assert_eq!(
+            names(&search_trie(&trie, "ironingot")),
+            vec!["ironingot", "irongot"]
+        );
+    }
+
+    #[test]
+    fn query_is_case_insensitive() {
+        let mut trie = TrieNode::default();
+        trie.insert("Iron Ingot");
+
+        assert_eq!(names(&search_trie(&trie, "iron ingot")), vec!["Iron Ingot"]);
+    }
+
+    fn item(name: &str) -> crate::ItemStack {
+        crate::ItemStack {
+            amount: 1,
+            metadata: 0,
+            unlocalized_name: Some(name.to_owned()),
+            localized_name: Some(name.to_owned()),
+        }
+    }
+
+    fn recipe(item_inputs: Vec<crate::ItemStack>, item_outputs: Vec<crate::ItemStack>) -> GTRecipe {
+        GTRecipe {
+            enabled: true,
+            duration: 1,
+            eut: 1,
+            item_inputs,
+            fluid_inputs: Vec::new(),
+            item_outputs,
+            fluid_outputs: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn query_does_not_duplicate_a_recipe_with_a_repeated_ingredient() {
+        // A recipe with the same item in two input slots must only show up
+        // once in `consumers`, not once per matching slot.
+        let root = Root {
+            sources: vec![RecipeSource::Gregtech {
+                machines: vec![crate::Machine {
+                    name: "Macerator".to_owned(),
+                    recipes: vec![recipe(
+                        vec![item("oreIron"), item("oreIron")],
+                        vec![item("dustIron")],
+                    )],
+                }],
+            }],
+        };
+
+        let index = ItemIndex::build(&root);
+        let result = index.query("oreIron");
+
+        assert_eq!(result.consumers.len(), 1);
+    }
+}